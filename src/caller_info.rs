@@ -0,0 +1,41 @@
+//! Source locations recorded for tasks, as a diagnostic aid for still-pending
+//! tasks.
+//!
+//! See [`WaitSpawner::pending_tasks`](crate::WaitSpawner::pending_tasks) for
+//! the public entry point that makes use of this.
+
+use std::fmt;
+
+/// A source location, as captured by the `caller_info!` macro at the place
+/// where the macro is invoked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallerInfo {
+    file: &'static str,
+    line: u32,
+    column: u32,
+}
+
+impl CallerInfo {
+    pub(crate) fn new(file: &'static str, line: u32, column: u32) -> Self {
+        CallerInfo { file, line, column }
+    }
+}
+
+impl fmt::Display for CallerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Captures the location of its own call site as a `CallerInfo`.
+///
+/// Built on `file!()`/`line!()`/`column!()` rather than `#[track_caller]`:
+/// the latter's feature gate postdates this crate's `futures_api`/
+/// `async_await`/`await_macro` gates by a couple of years, and no single
+/// toolchain accepts both, so callers must invoke this macro directly at
+/// their spawn site instead of through an intervening function call.
+macro_rules! caller_info {
+    () => {
+        crate::caller_info::CallerInfo::new(file!(), line!(), column!())
+    };
+}
@@ -0,0 +1,73 @@
+//! Configuration types for [`WaitSpawner`](crate::WaitSpawner)'s background
+//! draining behavior.
+
+use std::time::Duration;
+
+/// Parameters for the sleep-step backoff used while draining completed
+/// tasks: when a sweep finds nothing new to reap, the wait interval grows by
+/// `step` up to `max`; as soon as a sweep reaps something, it resets to
+/// `min`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SleepParams {
+    /// The interval to sleep for before the very first sweep.
+    pub sleep_period: Duration,
+    /// The smallest interval the backoff can reset to.
+    pub min: Duration,
+    /// The largest interval the backoff can grow to.
+    pub max: Duration,
+    /// How much the interval grows by on each idle sweep.
+    pub step: Duration,
+}
+
+impl SleepParams {
+    /// Creates a new set of backoff parameters.
+    pub fn new(sleep_period: Duration, min: Duration, max: Duration, step: Duration) -> Self {
+        SleepParams {
+            sleep_period,
+            min,
+            max,
+            step,
+        }
+    }
+
+    pub(crate) fn backoff(&mut self) {
+        self.sleep_period = (self.sleep_period + self.step).min(self.max);
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.sleep_period = self.min;
+    }
+}
+
+impl Default for SleepParams {
+    fn default() -> Self {
+        SleepParams::new(
+            Duration::from_millis(10),
+            Duration::from_millis(10),
+            Duration::from_millis(500),
+            Duration::from_millis(10),
+        )
+    }
+}
+
+/// Controls what a [`WaitSpawner`](crate::WaitSpawner)'s background drain
+/// loop does with a task's bookkeeping once the task has run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetentionMode {
+    /// Keep every task's bookkeeping around indefinitely, finished or not,
+    /// so it stays queryable after the fact.
+    KeepAll,
+    /// Drop a task's bookkeeping on the next sweep regardless of whether it
+    /// has finished yet -- a pure fire-and-forget pool that bounds memory at
+    /// the cost of being unable to wait on or inspect older tasks.
+    RemoveAll,
+    /// Drop a task's bookkeeping only once it has finished, leaving pending
+    /// tasks alone. The default.
+    RemoveFinished,
+}
+
+impl Default for RetentionMode {
+    fn default() -> Self {
+        RetentionMode::RemoveFinished
+    }
+}
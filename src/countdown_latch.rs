@@ -0,0 +1,139 @@
+//! An async countdown latch, for fork/join coordination finer-grained than
+//! waiting on a whole spawned task.
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use futures::future::Future;
+use futures_timer::Delay;
+
+use crate::waker_list::WakerList;
+
+struct LatchInner {
+    count: usize,
+    wakers: WakerList,
+}
+
+/// A latch that starts at a count of `N` and releases every waiter once it
+/// has been counted down to zero.
+///
+/// `CountDownLatch` is cheap to clone -- clones share the same underlying
+/// count -- so a clone can be handed to each spawned future that should call
+/// [`CountDownLatch::count_down`] on completion.
+#[derive(Clone)]
+pub struct CountDownLatch {
+    inner: Arc<Mutex<LatchInner>>,
+}
+
+impl CountDownLatch {
+    /// Creates a new latch with the given initial count.
+    ///
+    /// A latch created with a count of `0` is already released.
+    pub fn new(count: usize) -> Self {
+        CountDownLatch {
+            inner: Arc::new(Mutex::new(LatchInner {
+                count,
+                wakers: WakerList::new(),
+            })),
+        }
+    }
+
+    /// Decrements the count by one, waking every waiter if it reaches zero.
+    ///
+    /// Does nothing if the count is already zero.
+    pub fn count_down(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.count == 0 {
+            return;
+        }
+        inner.count -= 1;
+        if inner.count == 0 {
+            inner.wakers.wake_all();
+        }
+    }
+
+    /// The current count.
+    pub fn count(&self) -> usize {
+        self.inner.lock().unwrap().count
+    }
+
+    /// Returns a future that resolves once the count reaches zero.
+    ///
+    /// Resolves immediately if the count is already zero.
+    pub fn wait(&self) -> Wait {
+        Wait {
+            latch: self.clone(),
+            registered: None,
+        }
+    }
+
+    /// Like [`CountDownLatch::wait`], but gives up and returns `false` if the
+    /// count hasn't reached zero within `duration`. Returns `true` if the
+    /// latch was released in time.
+    pub async fn wait_timeout(&self, duration: Duration) -> bool {
+        let wait = self.wait();
+        let timeout = Delay::new(duration);
+        futures::pin_mut!(wait);
+        futures::pin_mut!(timeout);
+        match futures::future::select(wait, timeout).await {
+            futures::future::Either::Left(_) => true,
+            futures::future::Either::Right(_) => false,
+        }
+    }
+}
+
+/// Future returned by [`CountDownLatch::wait`]. Tracks its own
+/// last-registered waker so that repeated polls don't leak one `WakerList`
+/// entry each, and so that dropping it (e.g. `wait_timeout` timing out)
+/// removes its registration instead of leaving a stale entry behind.
+pub struct Wait {
+    latch: CountDownLatch,
+    registered: Option<Waker>,
+}
+
+impl Future for Wait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        let mut inner = this.latch.inner.lock().unwrap();
+        if inner.count == 0 {
+            Poll::Ready(())
+        } else {
+            inner.wakers.register(this.registered.as_ref(), cx.waker());
+            drop(inner);
+            this.registered = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Wait {
+    fn drop(&mut self) {
+        if let Some(waker) = self.registered.take() {
+            self.latch.inner.lock().unwrap().wakers.remove(&waker);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn wait_resolves_immediately_when_already_zero() {
+        let latch = CountDownLatch::new(0);
+        block_on(latch.wait());
+    }
+
+    #[test]
+    fn wait_timeout_distinguishes_release_from_timeout() {
+        let latch = CountDownLatch::new(1);
+        assert!(!block_on(latch.wait_timeout(Duration::from_millis(20))));
+        latch.count_down();
+        assert!(block_on(latch.wait_timeout(Duration::from_millis(20))));
+    }
+}
@@ -6,7 +6,16 @@
     warnings
 )]
 
+#[macro_use]
 mod caller_info;
+mod config;
+mod countdown_latch;
 mod wait_spawner;
+mod waker_list;
 
-pub use self::wait_spawner::WaitSpawner;
+pub use self::caller_info::CallerInfo;
+pub use self::config::{RetentionMode, SleepParams};
+pub use self::countdown_latch::CountDownLatch;
+pub use self::wait_spawner::{
+    PendingTask, PendingTasks, SpawnWithKeyError, TaskHandle, WaitSpawner, WaitSpawnerBuilder,
+};
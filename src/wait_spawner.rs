@@ -0,0 +1,707 @@
+//! A task spawner that remembers what it spawned, so callers can wait on it later.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Instant;
+
+use futures::future::{join_all, select_all, Future, FutureExt};
+use futures::task::{Spawn, SpawnError, SpawnExt};
+use futures_timer::Delay;
+
+use crate::caller_info::CallerInfo;
+use crate::config::{RetentionMode, SleepParams};
+use crate::waker_list::WakerList;
+
+/// Shared completion signal for a single spawned task.
+///
+/// Unlike a oneshot channel, a `Completion` can be polled by more than one
+/// waiter and more than once, which is what lets `wait_for`/`wait_any` be
+/// called repeatedly without consuming the task's bookkeeping.
+#[derive(Clone)]
+struct Completion(Arc<Mutex<CompletionInner>>);
+
+struct CompletionInner {
+    done: bool,
+    wakers: WakerList,
+}
+
+impl Completion {
+    fn new() -> Self {
+        Completion(Arc::new(Mutex::new(CompletionInner {
+            done: false,
+            wakers: WakerList::new(),
+        })))
+    }
+
+    fn mark_done(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.done = true;
+        inner.wakers.wake_all();
+    }
+
+    fn wait(&self) -> CompletionFuture {
+        CompletionFuture {
+            completion: self.clone(),
+            registered: None,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.0.lock().unwrap().done
+    }
+}
+
+/// Future returned by [`Completion::wait`]. Tracks its own last-registered
+/// waker so that repeated polls don't leak one `WakerList` entry each, and so
+/// that dropping it (e.g. because a `select_all`/timeout picked a different
+/// branch) removes its registration instead of leaving it to be woken for no
+/// reason once the task completes.
+struct CompletionFuture {
+    completion: Completion,
+    registered: Option<Waker>,
+}
+
+impl Future for CompletionFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        let mut inner = this.completion.0.lock().unwrap();
+        if inner.done {
+            Poll::Ready(())
+        } else {
+            inner.wakers.register(this.registered.as_ref(), cx.waker());
+            drop(inner);
+            this.registered = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for CompletionFuture {
+    fn drop(&mut self) {
+        if let Some(waker) = self.registered.take() {
+            self.completion.0.lock().unwrap().wakers.remove(&waker);
+        }
+    }
+}
+
+/// Shared cancellation flag for a single spawned task.
+#[derive(Clone)]
+struct CancelToken(Arc<Mutex<CancelState>>);
+
+struct CancelState {
+    cancelled: bool,
+    waker: Option<Waker>,
+}
+
+impl CancelToken {
+    fn new() -> Self {
+        CancelToken(Arc::new(Mutex::new(CancelState {
+            cancelled: false,
+            waker: None,
+        })))
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.lock().unwrap().cancelled
+    }
+
+    fn register(&self, waker: &Waker) {
+        self.0.lock().unwrap().waker = Some(waker.clone());
+    }
+
+    /// Flags the task for cancellation and wakes it so its next poll
+    /// short-circuits to `Poll::Ready` instead of waiting for the task's own
+    /// wakeup source.
+    fn cancel(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.cancelled = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Wraps a spawned future so that it resolves as soon as its `CancelToken` is
+/// cancelled, dropping the inner future without polling it further.
+struct Cancellable {
+    inner: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    token: CancelToken,
+}
+
+impl Future for Cancellable {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        if this.token.is_cancelled() {
+            this.inner = None;
+            return Poll::Ready(());
+        }
+        this.token.register(cx.waker());
+        match this.inner.as_mut() {
+            Some(fut) => match fut.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    this.inner = None;
+                    Poll::Ready(())
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+/// Bookkeeping kept for every task spawned through a `WaitSpawner`.
+struct TaskState {
+    caller_info: CallerInfo,
+    spawned_at: Instant,
+    completion: Completion,
+    /// Only set for tasks spawned with [`WaitSpawner::spawn_with_key`], which
+    /// are the only ones that can be individually cancelled.
+    cancel_token: Option<CancelToken>,
+}
+
+/// A snapshot of one still-pending task, as returned by
+/// [`WaitSpawner::pending_tasks`].
+pub struct PendingTask<K> {
+    /// The key the task was spawned under, or `None` for a task spawned
+    /// anonymously via [`WaitSpawner::spawn`].
+    pub key: Option<K>,
+    /// The source location recorded for the task: the `caller_info!()` call
+    /// inside [`WaitSpawner::spawn`] or [`WaitSpawner::spawn_with_key`]. It
+    /// tells anonymous and keyed tasks apart; it is not the location of the
+    /// code that called `spawn`/`spawn_with_key`.
+    pub caller_info: CallerInfo,
+    /// When the task was spawned.
+    pub spawned_at: Instant,
+}
+
+/// A snapshot of every still-pending task -- keyed or anonymous -- tracked by
+/// a `WaitSpawner`, returned by [`WaitSpawner::pending_tasks`].
+///
+/// Formatting it with `{}` dumps one line per task, longest-running first --
+/// handy for spotting which tasks have been outstanding longest during a hang.
+pub struct PendingTasks<K>(Vec<PendingTask<K>>);
+
+impl<K> PendingTasks<K> {
+    /// Iterates over the snapshot in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &PendingTask<K>> {
+        self.0.iter()
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for PendingTasks<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut tasks: Vec<&PendingTask<K>> = self.0.iter().collect();
+        tasks.sort_by_key(|t| t.spawned_at);
+        for task in tasks {
+            match &task.key {
+                Some(key) => write!(f, "{:>10.2?} pending -- key {}", task.spawned_at.elapsed(), key)?,
+                None => write!(f, "{:>10.2?} pending -- anonymous", task.spawned_at.elapsed())?,
+            }
+            writeln!(f, ", spawned at {}", task.caller_info)?;
+        }
+        Ok(())
+    }
+}
+
+/// A handle to a task spawned with [`WaitSpawner::spawn_with_key`].
+///
+/// Holding the handle isn't required to wait on or cancel the task -- the key
+/// alone is enough -- but it's a convenient place to hang the key off of so
+/// callers don't need to keep a separate copy around.
+pub struct TaskHandle<K> {
+    key: K,
+}
+
+impl<K> TaskHandle<K> {
+    /// The key this task was spawned under.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+/// Error returned by [`WaitSpawner::spawn_with_key`].
+#[derive(Debug)]
+pub enum SpawnWithKeyError {
+    /// A task spawned under the requested key is still running.
+    KeyInUse,
+    /// The underlying executor refused to spawn the future.
+    Spawn(SpawnError),
+}
+
+impl fmt::Display for SpawnWithKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpawnWithKeyError::KeyInUse => {
+                write!(f, "a task is already running under this key")
+            }
+            SpawnWithKeyError::Spawn(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SpawnWithKeyError {}
+
+impl From<SpawnError> for SpawnWithKeyError {
+    fn from(err: SpawnError) -> Self {
+        SpawnWithKeyError::Spawn(err)
+    }
+}
+
+/// Spawns futures onto an underlying executor while keeping track of them,
+/// so that other tasks can later wait for some or all of them to complete.
+///
+/// `K` is the type of key used by [`WaitSpawner::spawn_with_key`]; it can be
+/// left to its default of `()` for spawners that only ever use the unkeyed
+/// [`WaitSpawner::spawn`].
+pub struct WaitSpawner<Sp, K = ()> {
+    spawner: Sp,
+    /// Tasks spawned anonymously, via [`WaitSpawner::spawn`].
+    tasks: Arc<Mutex<Vec<TaskState>>>,
+    /// Tasks spawned under a key, via [`WaitSpawner::spawn_with_key`].
+    keyed_tasks: Arc<Mutex<HashMap<K, TaskState>>>,
+    sleep_params: Arc<Mutex<SleepParams>>,
+    retention_mode: RetentionMode,
+}
+
+impl<Sp, K> WaitSpawner<Sp, K>
+where
+    Sp: Spawn,
+    K: Eq + Hash,
+{
+    /// Creates a new `WaitSpawner` on top of the given executor, using the
+    /// default [`SleepParams`] and [`RetentionMode`]. Use
+    /// [`WaitSpawner::builder`] to customize either.
+    pub fn new(spawner: Sp) -> Self {
+        WaitSpawner::builder(spawner).build()
+    }
+
+    /// Starts building a `WaitSpawner` with a custom [`SleepParams`] and/or
+    /// [`RetentionMode`].
+    pub fn builder(spawner: Sp) -> WaitSpawnerBuilder<Sp, K> {
+        WaitSpawnerBuilder {
+            spawner,
+            sleep_params: SleepParams::default(),
+            retention_mode: RetentionMode::default(),
+            _key: std::marker::PhantomData,
+        }
+    }
+
+    /// Spawns a background task that periodically sweeps the spawner's
+    /// bookkeeping, applying this spawner's [`RetentionMode`] and backing off
+    /// between sweeps according to its [`SleepParams`] when a sweep finds
+    /// nothing to reap.
+    ///
+    /// The background task runs until the underlying executor is dropped; it
+    /// is meant for spawners that are kept around as a long-lived worker
+    /// pool rather than waited on and discarded.
+    pub fn start_draining(&mut self) -> Result<(), SpawnError>
+    where
+        K: Send + 'static,
+    {
+        let tasks = self.tasks.clone();
+        let keyed_tasks = self.keyed_tasks.clone();
+        let sleep_params = self.sleep_params.clone();
+        let retention_mode = self.retention_mode;
+
+        self.spawner.spawn(async move {
+            let mut last_done_count = 0;
+            loop {
+                let sleep_period = sleep_params.lock().unwrap().sleep_period;
+                Delay::new(sleep_period).await;
+
+                let progressed = sweep(&tasks, &keyed_tasks, retention_mode, &mut last_done_count);
+
+                {
+                    let mut params = sleep_params.lock().unwrap();
+                    if progressed {
+                        params.reset();
+                    } else {
+                        params.backoff();
+                    }
+                }
+            }
+        })
+    }
+
+    /// Spawns a future, tracking it so that a later call to [`WaitSpawner::wait`]
+    /// will block until it (and every other tracked future) completes.
+    pub fn spawn<Fut>(&mut self, future: Fut) -> Result<(), SpawnError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let completion = Completion::new();
+        let task_completion = completion.clone();
+        let wrapped = future.map(move |_| task_completion.mark_done());
+        self.spawner.spawn(wrapped)?;
+
+        self.tasks.lock().unwrap().push(TaskState {
+            caller_info: caller_info!(),
+            spawned_at: Instant::now(),
+            completion,
+            cancel_token: None,
+        });
+        Ok(())
+    }
+
+    /// Spawns a future under `key`, returning a handle to it.
+    ///
+    /// The task can later be singled out with [`WaitSpawner::wait_for`] or
+    /// [`WaitSpawner::wait_any`] without waiting for every other task tracked
+    /// by this spawner to finish.
+    ///
+    /// Returns [`SpawnWithKeyError::KeyInUse`] without spawning `future` if a
+    /// task spawned under the same `key` is still running -- otherwise the
+    /// still-running task would be silently orphaned: untracked by
+    /// `wait_for`/`cancel`/`pending_tasks`, and never cancellable again.
+    pub fn spawn_with_key<Fut>(
+        &mut self,
+        key: K,
+        future: Fut,
+    ) -> Result<TaskHandle<K>, SpawnWithKeyError>
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+        K: Clone,
+    {
+        {
+            let keyed_tasks = self.keyed_tasks.lock().unwrap();
+            if let Some(existing) = keyed_tasks.get(&key) {
+                if !existing.completion.is_done() {
+                    return Err(SpawnWithKeyError::KeyInUse);
+                }
+            }
+        }
+
+        let completion = Completion::new();
+        let task_completion = completion.clone();
+        let cancel_token = CancelToken::new();
+        let cancellable = Cancellable {
+            inner: Some(future.boxed()),
+            token: cancel_token.clone(),
+        };
+        let wrapped = cancellable.map(move |_| task_completion.mark_done());
+        self.spawner.spawn(wrapped)?;
+
+        self.keyed_tasks.lock().unwrap().insert(
+            key.clone(),
+            TaskState {
+                caller_info: caller_info!(),
+                spawned_at: Instant::now(),
+                completion,
+                cancel_token: Some(cancel_token),
+            },
+        );
+        Ok(TaskHandle { key })
+    }
+
+    /// Cancels the task spawned under `key`, if it's still running, dropping
+    /// it before it completes naturally. Returns whether a running task was
+    /// found and newly cancelled -- a task that was already cancelled (but
+    /// hasn't been re-polled to completion yet) reports `false`, since it
+    /// isn't still running in the sense this method is asking about.
+    ///
+    /// A cancelled task still counts as completed for the purposes of
+    /// [`WaitSpawner::wait`], [`WaitSpawner::wait_for`] and
+    /// [`WaitSpawner::wait_any`], so no waiter is left hanging.
+    pub fn cancel(&self, key: &K) -> bool {
+        match self.keyed_tasks.lock().unwrap().get(key) {
+            Some(task) if !task.completion.is_done() => match &task.cancel_token {
+                Some(token) if !token.is_cancelled() => {
+                    token.cancel();
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Cancels every currently tracked keyed task, returning how many were
+    /// still running.
+    pub fn cancel_all(&self) -> usize {
+        let keyed_tasks = self.keyed_tasks.lock().unwrap();
+        let mut cancelled = 0;
+        for task in keyed_tasks.values() {
+            if task.completion.is_done() {
+                continue;
+            }
+            if let Some(token) = &task.cancel_token {
+                if !token.is_cancelled() {
+                    token.cancel();
+                    cancelled += 1;
+                }
+            }
+        }
+        cancelled
+    }
+
+    /// Waits until every future tracked by this spawner -- keyed or not --
+    /// has completed.
+    pub async fn wait(&mut self) {
+        let completions: Vec<_> = {
+            let mut tasks = self.tasks.lock().unwrap();
+            let mut keyed_tasks = self.keyed_tasks.lock().unwrap();
+            tasks
+                .drain(..)
+                .map(|t| t.completion)
+                .chain(keyed_tasks.drain().map(|(_, t)| t.completion))
+                .collect()
+        };
+        join_all(completions.iter().map(Completion::wait)).await;
+    }
+
+    /// Waits until every task named in `keys` has completed, leaving tasks
+    /// outside that set untouched and still running.
+    pub async fn wait_for(&self, keys: &[K]) {
+        let completions = self.completions_for(keys);
+        join_all(completions.iter().map(Completion::wait)).await;
+    }
+
+    /// Waits until the first of the tasks named in `keys` completes, leaving
+    /// the rest untouched and still running.
+    ///
+    /// Does nothing if `keys` is empty or none of them name a task that is
+    /// still tracked by this spawner.
+    pub async fn wait_any(&self, keys: &[K]) {
+        let completions = self.completions_for(keys);
+        if completions.is_empty() {
+            return;
+        }
+        let waiters: Vec<_> = completions.iter().map(|c| c.wait().boxed()).collect();
+        select_all(waiters).await;
+    }
+
+    fn completions_for(&self, keys: &[K]) -> Vec<Completion> {
+        let keyed_tasks = self.keyed_tasks.lock().unwrap();
+        keys.iter()
+            .filter_map(|key| keyed_tasks.get(key).map(|t| t.completion.clone()))
+            .collect()
+    }
+
+    /// Takes a snapshot of every task -- keyed or anonymous -- that hasn't
+    /// completed yet, along with its key, its recorded [`CallerInfo`] (see
+    /// [`PendingTask::caller_info`]) and when it was spawned -- useful for
+    /// figuring out which tasks have outstanding work, and for how long, when
+    /// debugging a hang.
+    pub fn pending_tasks(&self) -> PendingTasks<K>
+    where
+        K: Clone,
+    {
+        let tasks = self.tasks.lock().unwrap();
+        let keyed_tasks = self.keyed_tasks.lock().unwrap();
+        let pending = tasks
+            .iter()
+            .filter(|task| !task.completion.is_done())
+            .map(|task| PendingTask {
+                key: None,
+                caller_info: task.caller_info,
+                spawned_at: task.spawned_at,
+            })
+            .chain(
+                keyed_tasks
+                    .iter()
+                    .filter(|(_, task)| !task.completion.is_done())
+                    .map(|(key, task)| PendingTask {
+                        key: Some(key.clone()),
+                        caller_info: task.caller_info,
+                        spawned_at: task.spawned_at,
+                    }),
+            )
+            .collect();
+        PendingTasks(pending)
+    }
+}
+
+/// Performs one sweep of a spawner's bookkeeping according to `retention_mode`,
+/// returning whether the sweep made any progress (found something to reap, or
+/// for [`RetentionMode::KeepAll`], found something newly finished).
+///
+/// `last_done_count` carries the done-count observed by the previous sweep --
+/// only used by [`RetentionMode::KeepAll`], which never reaps anything, so
+/// "progress" has to mean a change since last time rather than a standing
+/// count of everything that's ever finished.
+fn sweep<K: Eq + Hash>(
+    tasks: &Mutex<Vec<TaskState>>,
+    keyed_tasks: &Mutex<HashMap<K, TaskState>>,
+    retention_mode: RetentionMode,
+    last_done_count: &mut usize,
+) -> bool {
+    match retention_mode {
+        RetentionMode::KeepAll => {
+            let tasks = tasks.lock().unwrap();
+            let keyed_tasks = keyed_tasks.lock().unwrap();
+            let done_count = tasks.iter().filter(|t| t.completion.is_done()).count()
+                + keyed_tasks
+                    .values()
+                    .filter(|t| t.completion.is_done())
+                    .count();
+            let progressed = done_count != *last_done_count;
+            *last_done_count = done_count;
+            progressed
+        }
+        RetentionMode::RemoveAll => {
+            let mut tasks = tasks.lock().unwrap();
+            let mut keyed_tasks = keyed_tasks.lock().unwrap();
+            let progressed = !tasks.is_empty() || !keyed_tasks.is_empty();
+            tasks.clear();
+            keyed_tasks.clear();
+            progressed
+        }
+        RetentionMode::RemoveFinished => {
+            let mut tasks = tasks.lock().unwrap();
+            let before = tasks.len();
+            tasks.retain(|t| !t.completion.is_done());
+            let tasks_progressed = tasks.len() != before;
+
+            let mut keyed_tasks = keyed_tasks.lock().unwrap();
+            let before = keyed_tasks.len();
+            keyed_tasks.retain(|_, t| !t.completion.is_done());
+            let keyed_progressed = keyed_tasks.len() != before;
+
+            tasks_progressed || keyed_progressed
+        }
+    }
+}
+
+/// Builder for a [`WaitSpawner`], returned by [`WaitSpawner::builder`].
+pub struct WaitSpawnerBuilder<Sp, K = ()> {
+    spawner: Sp,
+    sleep_params: SleepParams,
+    retention_mode: RetentionMode,
+    _key: std::marker::PhantomData<K>,
+}
+
+impl<Sp, K> WaitSpawnerBuilder<Sp, K>
+where
+    Sp: Spawn,
+    K: Eq + Hash,
+{
+    /// Sets the backoff parameters used by [`WaitSpawner::start_draining`].
+    pub fn sleep_params(mut self, sleep_params: SleepParams) -> Self {
+        self.sleep_params = sleep_params;
+        self
+    }
+
+    /// Sets the retention mode used by [`WaitSpawner::start_draining`].
+    pub fn retention_mode(mut self, retention_mode: RetentionMode) -> Self {
+        self.retention_mode = retention_mode;
+        self
+    }
+
+    /// Builds the configured `WaitSpawner`.
+    pub fn build(self) -> WaitSpawner<Sp, K> {
+        WaitSpawner {
+            spawner: self.spawner,
+            tasks: Arc::new(Mutex::new(Vec::new())),
+            keyed_tasks: Arc::new(Mutex::new(HashMap::new())),
+            sleep_params: Arc::new(Mutex::new(self.sleep_params)),
+            retention_mode: self.retention_mode,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::{block_on, ThreadPool};
+
+    fn pool() -> ThreadPool {
+        ThreadPool::new().expect("failed to create thread pool")
+    }
+
+    #[test]
+    fn cancel_reports_whether_still_running() {
+        let mut spawner: WaitSpawner<ThreadPool, &'static str> = WaitSpawner::new(pool());
+
+        spawner.spawn_with_key("done", async {}).unwrap();
+        block_on(spawner.wait_for(&["done"]));
+        assert!(!spawner.cancel(&"done"));
+
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        spawner
+            .spawn_with_key("running", async {
+                let _ = rx.await;
+            })
+            .unwrap();
+        assert!(spawner.cancel(&"running"));
+        assert!(!spawner.cancel(&"running"));
+        drop(tx);
+    }
+
+    #[test]
+    fn cancel_all_only_counts_still_running_tasks() {
+        let mut spawner: WaitSpawner<ThreadPool, u32> = WaitSpawner::new(pool());
+
+        spawner.spawn_with_key(1, async {}).unwrap();
+        block_on(spawner.wait_for(&[1]));
+
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        spawner
+            .spawn_with_key(2, async {
+                let _ = rx.await;
+            })
+            .unwrap();
+
+        assert_eq!(spawner.cancel_all(), 1);
+        drop(tx);
+    }
+
+    #[test]
+    fn spawn_with_key_rejects_collision_while_running() {
+        let mut spawner: WaitSpawner<ThreadPool, &'static str> = WaitSpawner::new(pool());
+
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        spawner
+            .spawn_with_key("k", async {
+                let _ = rx.await;
+            })
+            .unwrap();
+
+        match spawner.spawn_with_key("k", async {}) {
+            Err(SpawnWithKeyError::KeyInUse) => {}
+            other => panic!("expected KeyInUse, got {:?}", other.map(|_| ())),
+        }
+        drop(tx);
+    }
+
+    #[test]
+    fn pending_tasks_sorted_longest_running_first() {
+        let mut spawner: WaitSpawner<ThreadPool, u32> = WaitSpawner::new(pool());
+
+        let (tx1, rx1) = futures::channel::oneshot::channel::<()>();
+        spawner
+            .spawn_with_key(1, async {
+                let _ = rx1.await;
+            })
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        let (tx2, rx2) = futures::channel::oneshot::channel::<()>();
+        spawner
+            .spawn_with_key(2, async {
+                let _ = rx2.await;
+            })
+            .unwrap();
+
+        let pending = spawner.pending_tasks();
+        // `iter()` is explicitly unordered (keyed tasks come from a HashMap),
+        // so only compare the set of keys here -- the Display dump below is
+        // what actually promises longest-running-first ordering.
+        let mut keys: Vec<u32> = pending.iter().filter_map(|t| t.key).collect();
+        keys.sort();
+        assert_eq!(keys, vec![1, 2]);
+
+        let dump = format!("{}", pending);
+        assert!(dump.find("key 1").unwrap() < dump.find("key 2").unwrap());
+
+        drop(tx1);
+        drop(tx2);
+    }
+}
@@ -0,0 +1,41 @@
+//! A list of wakers for broadcasting a single event to many waiters, without
+//! letting stale entries pile up across repeated polls or dropped waiters.
+
+use std::task::Waker;
+
+#[derive(Default)]
+pub(crate) struct WakerList(Vec<Waker>);
+
+impl WakerList {
+    pub(crate) fn new() -> Self {
+        WakerList(Vec::new())
+    }
+
+    /// Registers `waker` as waiting for the event, replacing `previous` (the
+    /// waiter's own last registration, if any) so that polling the same
+    /// waiter repeatedly doesn't accumulate one entry per poll.
+    pub(crate) fn register(&mut self, previous: Option<&Waker>, waker: &Waker) {
+        if let Some(previous) = previous {
+            if previous.will_wake(waker) {
+                return;
+            }
+            self.remove(previous);
+        }
+        self.0.push(waker.clone());
+    }
+
+    /// Removes a waiter's registration, e.g. because the waiter was dropped
+    /// before the event fired.
+    pub(crate) fn remove(&mut self, waker: &Waker) {
+        if let Some(pos) = self.0.iter().position(|w| w.will_wake(waker)) {
+            self.0.remove(pos);
+        }
+    }
+
+    /// Wakes every registered waiter and clears the list.
+    pub(crate) fn wake_all(&mut self) {
+        for waker in self.0.drain(..) {
+            waker.wake();
+        }
+    }
+}